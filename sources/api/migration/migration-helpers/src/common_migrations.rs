@@ -77,6 +77,7 @@ impl Migration for AddPrefixesMigration {
 #[cfg(test)]
 mod test_add_prefixes_migration {
     use super::AddPrefixesMigration;
+    use crate::test_helpers::assert_setting_unchanged;
     use crate::{Migration, MigrationData};
     use maplit::hashmap;
     use std::collections::HashMap;
@@ -94,8 +95,11 @@ mod test_add_prefixes_migration {
         };
         // Run backward, e.g. downgrade, to test that the right keys are removed
         let result = AddPrefixesMigration(vec!["remove.me"])
-            .backward(data)
+            .backward(data.clone())
             .unwrap();
+        for key in ["keep.me.a", "keep.this.c"] {
+            assert_setting_unchanged(&data.data, &result.data, key);
+        }
         assert_eq!(
             result.data,
             hashmap! {
@@ -118,8 +122,11 @@ mod test_add_prefixes_migration {
         };
         // Run backward, e.g. downgrade, to test that the right keys are removed
         let result = AddPrefixesMigration(vec!["remove.me", "remove.this"])
-            .backward(data)
+            .backward(data.clone())
             .unwrap();
+        for key in ["keep.me.a", "keep.this.c"] {
+            assert_setting_unchanged(&data.data, &result.data, key);
+        }
         assert_eq!(
             result.data,
             hashmap! {
@@ -190,6 +197,144 @@ impl Migration for RemoveSettingsMigration<'_> {
 
 // =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=
 
+/// We use this migration when a setting's value needs to exist at a second path, for example
+/// during a transition period where both the old and new names are read.  Each pair is given as
+/// (source, destination).
+pub struct CopySettingMigration<'a>(pub &'a [(&'static str, &'static str)]);
+
+impl Migration for CopySettingMigration<'_> {
+    /// Copy each source setting's value to its destination.  We don't overwrite a destination that
+    /// already has a value, since that may have been set intentionally.
+    fn forward(&mut self, mut input: MigrationData) -> Result<MigrationData> {
+        for (src, dst) in self.0 {
+            if input.data.contains_key(*dst) {
+                println!("Found existing value for {}, not copying {}", dst, src);
+                continue;
+            }
+            if let Some(data) = input.data.get(*src).cloned() {
+                println!("Copied {} to {}, which was set to '{}'", src, dst, data);
+                input.data.insert(dst.to_string(), data);
+            } else {
+                println!("Found no {} to copy", src);
+            }
+        }
+        Ok(input)
+    }
+
+    /// Older versions don't know about the destination settings; we remove them so that old
+    /// versions don't see them and fail deserialization.  The source settings are left alone.
+    fn backward(&mut self, mut input: MigrationData) -> Result<MigrationData> {
+        for (_, dst) in self.0 {
+            if let Some(data) = input.data.remove(*dst) {
+                println!("Removed {}, which was set to '{}'", dst, data);
+            } else {
+                println!("Found no {} to remove", dst);
+            }
+        }
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod test_copy_setting {
+    use super::CopySettingMigration;
+    use crate::test_helpers::assert_setting_unchanged;
+    use crate::{Migration, MigrationData};
+    use maplit::hashmap;
+    use std::collections::HashMap;
+
+    #[test]
+    fn forward() {
+        let data = MigrationData {
+            data: hashmap! {
+                "old.name".into() => "hi".into(),
+                "keep.me".into() => 0.into(),
+            },
+            metadata: HashMap::new(),
+        };
+        let result = CopySettingMigration(&[("old.name", "new.name")])
+            .forward(data.clone())
+            .unwrap();
+        for key in ["old.name", "keep.me"] {
+            assert_setting_unchanged(&data.data, &result.data, key);
+        }
+        assert_eq!(
+            result.data,
+            hashmap! {
+                "old.name".into() => "hi".into(),
+                "new.name".into() => "hi".into(),
+                "keep.me".into() => 0.into(),
+            }
+        );
+    }
+
+    #[test]
+    fn forward_existing_destination() {
+        let data = MigrationData {
+            data: hashmap! {
+                "old.name".into() => "hi".into(),
+                "new.name".into() => "there".into(),
+            },
+            metadata: HashMap::new(),
+        };
+        let result = CopySettingMigration(&[("old.name", "new.name")])
+            .forward(data.clone())
+            .unwrap();
+        assert_setting_unchanged(&data.data, &result.data, "new.name");
+        assert_eq!(
+            result.data,
+            hashmap! {
+                "old.name".into() => "hi".into(),
+                "new.name".into() => "there".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn forward_no_source() {
+        let data = MigrationData {
+            data: hashmap! {
+                "keep.me".into() => 0.into(),
+            },
+            metadata: HashMap::new(),
+        };
+        let result = CopySettingMigration(&[("old.name", "new.name")])
+            .forward(data)
+            .unwrap();
+        assert_eq!(
+            result.data,
+            hashmap! {
+                "keep.me".into() => 0.into(),
+            }
+        );
+    }
+
+    #[test]
+    fn backward() {
+        let data = MigrationData {
+            data: hashmap! {
+                "old.name".into() => "hi".into(),
+                "new.name".into() => "hi".into(),
+                "other.old".into() => 1.into(),
+                "other.new".into() => 1.into(),
+            },
+            metadata: HashMap::new(),
+        };
+        let result = CopySettingMigration(&[("old.name", "new.name"), ("other.old", "other.new")])
+            .backward(data)
+            .unwrap();
+        assert_eq!(
+            result.data,
+            hashmap! {
+                "old.name".into() => "hi".into(),
+                "other.old".into() => 1.into(),
+            }
+        );
+    }
+}
+
+// =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=   =^..^=
+
 /// We use this migration when we replace a setting's old string value with a new string value.
 pub struct ReplaceStringMigration {
     pub setting: &'static str,
@@ -13,6 +13,8 @@ mod args;
 pub mod common_migrations;
 mod datastore_helper;
 pub mod error;
+#[cfg(any(test, feature = "testhelpers"))]
+pub mod test_helpers;
 
 use snafu::ResultExt;
 use std::collections::HashMap;
@@ -127,6 +129,27 @@ impl fmt::Display for MigrationType {
     }
 }
 
+/// Runs a list of migrations as though they were a single migration.  On upgrade they run in the
+/// given order, and on downgrade they run in reverse order, so each migration sees the data it
+/// expects.  If any of them fails, the error is returned instead of partially migrated data.
+struct BatchMigration<'a, 'b>(&'a mut [&'b mut dyn Migration]);
+
+impl Migration for BatchMigration<'_, '_> {
+    fn forward(&mut self, mut input: MigrationData) -> Result<MigrationData> {
+        for migration in self.0.iter_mut() {
+            input = migration.forward(input)?;
+        }
+        Ok(input)
+    }
+
+    fn backward(&mut self, mut input: MigrationData) -> Result<MigrationData> {
+        for migration in self.0.iter_mut().rev() {
+            input = migration.backward(input)?;
+        }
+        Ok(input)
+    }
+}
+
 /// This is the primary entry point for migration authors.  When you've implemented the Migration
 /// trait, you should just be able to pass it to this function from your main function and let it
 /// take care of the rest.  The migration runner will pass in the appropriate datastore paths and
@@ -135,3 +158,163 @@ pub fn migrate(migration: impl Migration) -> Result<()> {
     let args = parse_args(env::args())?;
     run_migration(migration, &args)
 }
+
+/// Like `migrate`, but for migration binaries that need to apply several migrations together.
+/// The migrations are applied to each set of data in order (reverse order on downgrade), and that
+/// data is only written once every migration has succeeded on it.  If any of them fails, the whole
+/// migration fails, just as it would for a single migration.
+pub fn batch_migrate(migrations: &mut [&mut dyn Migration]) -> Result<()> {
+    migrate(BatchMigration(migrations))
+}
+
+#[cfg(test)]
+mod test_batch_migration {
+    use super::{BatchMigration, Migration, MigrationData, Result};
+    use crate::common_migrations::{AddSettingsMigration, RemoveSettingsMigration};
+    use crate::error;
+    use maplit::hashmap;
+    use std::collections::HashMap;
+
+    /// Appends its name to the "order" setting so tests can see the order migrations ran in.
+    struct RecordMigration(&'static str);
+
+    impl RecordMigration {
+        fn record(&self, mut input: MigrationData) -> MigrationData {
+            let order = input
+                .data
+                .get("order")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let order = format!("{}{}", order, self.0);
+            input.data.insert("order".into(), order.into());
+            input
+        }
+    }
+
+    impl Migration for RecordMigration {
+        fn forward(&mut self, input: MigrationData) -> Result<MigrationData> {
+            Ok(self.record(input))
+        }
+
+        fn backward(&mut self, input: MigrationData) -> Result<MigrationData> {
+            Ok(self.record(input))
+        }
+    }
+
+    struct FailMigration;
+
+    impl Migration for FailMigration {
+        fn forward(&mut self, _input: MigrationData) -> Result<MigrationData> {
+            error::MigrationSnafu { msg: "forward" }.fail()
+        }
+
+        fn backward(&mut self, _input: MigrationData) -> Result<MigrationData> {
+            error::MigrationSnafu { msg: "backward" }.fail()
+        }
+    }
+
+    struct UnreachableMigration;
+
+    impl Migration for UnreachableMigration {
+        fn forward(&mut self, _input: MigrationData) -> Result<MigrationData> {
+            unreachable!("migration ran after an earlier one failed")
+        }
+
+        fn backward(&mut self, _input: MigrationData) -> Result<MigrationData> {
+            unreachable!("migration ran after an earlier one failed")
+        }
+    }
+
+    fn empty_data() -> MigrationData {
+        MigrationData {
+            data: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn forward_in_order() {
+        let result = BatchMigration(&mut [
+            &mut RecordMigration("a"),
+            &mut RecordMigration("b"),
+            &mut RecordMigration("c"),
+        ])
+        .forward(empty_data())
+        .unwrap();
+        assert_eq!(result.data, hashmap! { "order".into() => "abc".into() });
+    }
+
+    #[test]
+    fn backward_in_reverse_order() {
+        let result = BatchMigration(&mut [
+            &mut RecordMigration("a"),
+            &mut RecordMigration("b"),
+            &mut RecordMigration("c"),
+        ])
+        .backward(empty_data())
+        .unwrap();
+        assert_eq!(result.data, hashmap! { "order".into() => "cba".into() });
+    }
+
+    #[test]
+    fn combined() {
+        let data = MigrationData {
+            data: hashmap! {
+                "old.setting".into() => 0.into(),
+                "new.setting".into() => 1.into(),
+                "keep.me".into() => 2.into(),
+            },
+            metadata: HashMap::new(),
+        };
+        let result = BatchMigration(&mut [
+            &mut RemoveSettingsMigration(&["old.setting"]),
+            &mut AddSettingsMigration(&["new.setting"]),
+        ])
+        .forward(data.clone())
+        .unwrap();
+        assert_eq!(
+            result.data,
+            hashmap! {
+                "new.setting".into() => 1.into(),
+                "keep.me".into() => 2.into(),
+            }
+        );
+
+        let result = BatchMigration(&mut [
+            &mut RemoveSettingsMigration(&["old.setting"]),
+            &mut AddSettingsMigration(&["new.setting"]),
+        ])
+        .backward(data)
+        .unwrap();
+        assert_eq!(
+            result.data,
+            hashmap! {
+                "old.setting".into() => 0.into(),
+                "keep.me".into() => 2.into(),
+            }
+        );
+    }
+
+    #[test]
+    fn partial_failure() {
+        // A failure stops the batch and returns the failing migration's error rather than any
+        // partially migrated data, and the migrations after it never run.
+        let err = BatchMigration(&mut [
+            &mut RecordMigration("a"),
+            &mut FailMigration,
+            &mut UnreachableMigration,
+        ])
+        .forward(empty_data())
+        .unwrap_err();
+        assert!(matches!(err, error::Error::Migration { ref msg } if msg == "forward"));
+
+        let err = BatchMigration(&mut [
+            &mut UnreachableMigration,
+            &mut FailMigration,
+            &mut RecordMigration("a"),
+        ])
+        .backward(empty_data())
+        .unwrap_err();
+        assert!(matches!(err, error::Error::Migration { ref msg } if msg == "backward"));
+    }
+}
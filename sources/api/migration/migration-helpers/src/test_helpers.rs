@@ -0,0 +1,76 @@
+//! Helpers for testing migrations.  Migration binaries can use these in their tests by enabling
+//! the `testhelpers` feature of migration-helpers in their dev-dependencies.
+
+use datastore::Value;
+use std::collections::HashMap;
+
+/// Panics with a descriptive message if `key` doesn't have the same value in `before` and `after`,
+/// including if it was added or removed.  Migration tests can use this to make sure a migration
+/// doesn't touch settings it isn't meant to.
+pub fn assert_setting_unchanged(
+    before: &HashMap<String, Value>,
+    after: &HashMap<String, Value>,
+    key: &str,
+) {
+    let before_value = before.get(key);
+    let after_value = after.get(key);
+    if before_value != after_value {
+        panic!(
+            "Setting '{}' was changed by migration: before {}, after {}",
+            key,
+            describe(before_value),
+            describe(after_value),
+        );
+    }
+}
+
+fn describe(value: Option<&Value>) -> String {
+    match value {
+        Some(value) => format!("'{}'", value),
+        None => "unset".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::assert_setting_unchanged;
+    use maplit::hashmap;
+
+    #[test]
+    fn unchanged() {
+        let before = hashmap! {
+            "keep.me".into() => "hi".into(),
+            "change.me".into() => 0.into(),
+        };
+        let after = hashmap! {
+            "keep.me".into() => "hi".into(),
+            "change.me".into() => 1.into(),
+        };
+        assert_setting_unchanged(&before, &after, "keep.me");
+        assert_setting_unchanged(&before, &after, "not.there");
+    }
+
+    #[test]
+    #[should_panic(expected = "Setting 'change.me' was changed by migration")]
+    fn changed() {
+        let before = hashmap! { "change.me".into() => 0.into() };
+        let after = hashmap! { "change.me".into() => 1.into() };
+        assert_setting_unchanged(&before, &after, "change.me");
+    }
+
+    #[test]
+    #[should_panic(expected = "before '0', after unset")]
+    fn removed() {
+        let before = hashmap! { "remove.me".into() => 0.into() };
+        let after = hashmap! {};
+        assert_setting_unchanged(&before, &after, "remove.me");
+    }
+
+    #[test]
+    #[should_panic(expected = "before unset, after '0'")]
+    fn added() {
+        let before = hashmap! {};
+        let after = hashmap! { "add.me".into() => 0.into() };
+        assert_setting_unchanged(&before, &after, "add.me");
+    }
+}